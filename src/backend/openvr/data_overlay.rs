@@ -0,0 +1,441 @@
+use std::{
+    collections::HashMap,
+    io::{Read, Seek, SeekFrom},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use ovr_overlay::{overlay::OverlayManager, sys::VROverlayHandle_t};
+use vulkano::{
+    buffer::{Buffer, BufferCreateInfo, BufferUsage, Subbuffer},
+    command_buffer::{AutoCommandBufferBuilder, CommandBufferUsage, CopyBufferToImageInfo},
+    image::{Image, ImageCreateInfo, ImageUsage},
+    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter},
+    sync::GpuFuture,
+};
+
+use crate::{graphics::WlxGraphics, state::AppState};
+
+use super::font;
+
+/// A single named field read out of a memory-mapped struct: byte offset
+/// plus how to interpret the bytes there. The caller configures this
+/// against whatever struct layout the external producer is writing.
+pub enum ShmField {
+    F32 { offset: usize },
+    I32 { offset: usize },
+}
+
+/// Where a `DataOverlay` pulls its values from each frame. All three are
+/// just different ways to get a flat set of named fields; formatting stays
+/// in the widget spec regardless of the source.
+pub enum DataSource {
+    /// A memory-mapped struct, read by field offset.
+    Shm {
+        mmap: memmap2::Mmap,
+        fields: Vec<(String, ShmField)>,
+    },
+    /// A named pipe carrying one JSON object per line.
+    Pipe { reader: std::fs::File, buf: String },
+    /// A plain file being appended to with a JSON line stream, tailed from
+    /// the last read position.
+    JsonLines { path: PathBuf, offset: u64 },
+}
+
+/// A single value read from a `DataSource` this frame. `Missing` is
+/// distinct from a present-but-zero value so widgets can render the
+/// sentinel/uninitialized case (e.g. "---") instead of "0".
+#[derive(Clone)]
+pub enum Value {
+    Missing,
+    Number(f64),
+    Text(String),
+}
+
+impl Value {
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    fn from_json(v: &serde_json::Value) -> Self {
+        match v {
+            serde_json::Value::Number(n) => n.as_f64().map(Value::Number).unwrap_or(Value::Missing),
+            serde_json::Value::String(s) => Value::Text(s.clone()),
+            serde_json::Value::Null => Value::Missing,
+            other => Value::Text(other.to_string()),
+        }
+    }
+}
+
+/// How a raw `Value` becomes the string drawn on screen. Kept separate
+/// from the data source so the same feed can back a raw readout in one
+/// widget and a "+1 Lap"-style derived label in another.
+pub enum Format {
+    Raw,
+    /// `decimals` digits after the point; falls back to `sentinel_text`
+    /// when the field is missing.
+    Number { decimals: usize, sentinel_text: String },
+    /// Renders a lap/delta style value: whole laps ahead show as
+    /// `"+N Lap"` / `"-N Lap"`, otherwise the signed fraction of a lap to
+    /// three decimals (the value is read as a lap count, not a time).
+    LapDelta,
+}
+
+impl Format {
+    fn render(&self, value: &Value) -> String {
+        match (self, value) {
+            (Format::Number { sentinel_text, .. }, Value::Missing) => sentinel_text.clone(),
+            (_, Value::Missing) => "---".to_string(),
+            (Format::Raw, Value::Text(s)) => s.clone(),
+            (Format::Raw, Value::Number(n)) => n.to_string(),
+            (Format::Number { decimals, .. }, v) => {
+                format!("{:.*}", decimals, v.as_f64().unwrap_or(0.0))
+            }
+            (Format::LapDelta, v) => format_lap_delta(v.as_f64().unwrap_or(0.0)),
+        }
+    }
+}
+
+fn format_lap_delta(laps_behind: f64) -> String {
+    let whole_laps = laps_behind.trunc();
+    if whole_laps.abs() >= 1.0 {
+        format!("{:+.0} Lap", -whole_laps)
+    } else {
+        format!("{:+.3}", -laps_behind)
+    }
+}
+
+/// A single declarative widget. Composed into a tree under `DataOverlay`
+/// and bound to a named field in the data source; a `Table` draws the rows
+/// of the source's `rows` array.
+pub enum Widget {
+    Label { field: String, format: Format, rect: Rect },
+    Gauge { field: String, min: f64, max: f64, rect: Rect },
+    /// A leaderboard-style table: each column is a named field resolved
+    /// against one entry of the source's `rows` array, mirroring the
+    /// place/name/delta rows of a sim-racing timing HUD. `rows_field` is
+    /// not consulted when reading rows. Only the first `max_rows` rows are
+    /// drawn; the table does not scroll.
+    Table {
+        rows_field: String,
+        columns: Vec<(String, Format)>,
+        max_rows: usize,
+        rect: Rect,
+    },
+}
+
+/// Pixel rectangle a widget draws itself into, in the overlay's own
+/// framebuffer space.
+#[derive(Clone, Copy)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+/// A data-driven HUD overlay: a widget tree rendered from values pulled
+/// from an external `DataSource` each frame, rather than from a mirrored
+/// desktop window. Used for things like sim-racing dashboards or
+/// system-monitor panels that float in VR.
+///
+/// Mirrors the render/after_render split the desktop mirror overlays use:
+/// `render` pulls fresh values and rasterizes the widget tree into a CPU
+/// framebuffer, `after_render` uploads it and submits the overlay texture.
+pub struct DataOverlay {
+    pub name: String,
+    pub want_visible: bool,
+    handle: VROverlayHandle_t,
+    target: Arc<Image>,
+    extent: [u32; 2],
+    framebuffer: Vec<u8>,
+    source: DataSource,
+    widgets: Vec<Widget>,
+    values: HashMap<String, Value>,
+    rows: Vec<HashMap<String, Value>>,
+}
+
+impl DataOverlay {
+    pub fn new(
+        overlay_mngr: &mut OverlayManager,
+        graphics: &Arc<WlxGraphics>,
+        name: impl Into<String>,
+        extent: [u32; 2],
+        source: DataSource,
+        widgets: Vec<Widget>,
+    ) -> Self {
+        let name = name.into();
+        let handle = overlay_mngr
+            .create_overlay(&format!("wlx-overlay-s.data.{name}"), &name)
+            .expect("failed to create data overlay");
+
+        let target = Image::new(
+            graphics.memory_allocator.clone(),
+            ImageCreateInfo {
+                extent: [extent[0], extent[1], 1],
+                format: vulkano::format::Format::R8G8B8A8_UNORM,
+                usage: ImageUsage::TRANSFER_DST | ImageUsage::SAMPLED,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
+                ..Default::default()
+            },
+        )
+        .expect("failed to allocate data overlay target");
+
+        Self {
+            name,
+            want_visible: true,
+            handle,
+            target,
+            extent,
+            framebuffer: vec![0u8; (extent[0] * extent[1] * 4) as usize],
+            source,
+            widgets,
+            values: HashMap::new(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Pull fresh values from the data source and rasterize the widget
+    /// tree into the CPU framebuffer. Participates in the same
+    /// `o.render(&mut state)` pass as the desktop mirror overlays.
+    pub fn render(&mut self, _state: &mut AppState) {
+        self.poll_source();
+        self.framebuffer.fill(0);
+
+        for i in 0..self.widgets.len() {
+            match &self.widgets[i] {
+                Widget::Label { field, format, rect } => {
+                    let text = format.render(self.values.get(field).unwrap_or(&Value::Missing));
+                    let rect = *rect;
+                    self.draw_text(rect, &text, [255, 255, 255, 255]);
+                }
+                Widget::Gauge { field, min, max, rect } => {
+                    let value = self.values.get(field).and_then(Value::as_f64).unwrap_or(*min);
+                    let fill = ((value - min) / (max - min).max(f64::EPSILON)).clamp(0.0, 1.0);
+                    let rect = *rect;
+                    self.draw_gauge(rect, fill as f32);
+                }
+                Widget::Table {
+                    columns,
+                    max_rows,
+                    rect,
+                    ..
+                } => {
+                    let rect = *rect;
+                    let row_h = (rect.h / (*max_rows).max(1) as u32).max(1);
+                    for (row_idx, row) in self.rows.iter().take(*max_rows).enumerate() {
+                        let row_rect = Rect {
+                            x: rect.x,
+                            y: rect.y + row_idx as u32 * row_h,
+                            w: rect.w,
+                            h: row_h,
+                        };
+                        let col_w = (rect.w / columns.len().max(1) as u32).max(1);
+                        for (col_idx, (field, format)) in columns.iter().enumerate() {
+                            let text = format.render(row.get(field).unwrap_or(&Value::Missing));
+                            let cell_rect = Rect {
+                                x: row_rect.x + col_idx as u32 * col_w,
+                                y: row_rect.y,
+                                w: col_w,
+                                h: row_rect.h,
+                            };
+                            self.draw_text(cell_rect, &text, [255, 255, 255, 255]);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Upload the framebuffer rasterized by `render` and submit it as the
+    /// overlay's texture.
+    pub fn after_render(&mut self, overlay_mngr: &mut OverlayManager, graphics: &Arc<WlxGraphics>) {
+        let upload: Subbuffer<[u8]> = Buffer::from_iter(
+            graphics.memory_allocator.clone(),
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_SRC,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+            self.framebuffer.iter().copied(),
+        )
+        .expect("failed to stage data overlay framebuffer");
+
+        let mut cmd = AutoCommandBufferBuilder::primary(
+            &graphics.command_buffer_allocator,
+            graphics.queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .expect("failed to start data overlay command buffer");
+
+        cmd.copy_buffer_to_image(CopyBufferToImageInfo::buffer_image(
+            upload,
+            self.target.clone(),
+        ))
+        .expect("failed to queue data overlay upload");
+
+        let cmd = cmd.build().expect("failed to build data overlay command buffer");
+        cmd.execute(graphics.queue.clone())
+            .expect("failed to submit data overlay command buffer")
+            .then_signal_fence_and_flush()
+            .expect("failed to flush data overlay command buffer")
+            .wait(None)
+            .expect("failed to wait for data overlay command buffer");
+
+        let _ = overlay_mngr.set_visibility(self.handle, self.want_visible);
+    }
+
+    fn poll_source(&mut self) {
+        match &mut self.source {
+            DataSource::Shm { mmap, fields } => {
+                let bytes = mmap.as_ref();
+                let mut values = HashMap::with_capacity(fields.len());
+                for (name, field) in fields.iter() {
+                    let value = match *field {
+                        ShmField::F32 { offset } => bytes
+                            .get(offset..offset + 4)
+                            .map(|b| Value::Number(f32::from_le_bytes(b.try_into().unwrap()) as f64)),
+                        ShmField::I32 { offset } => bytes
+                            .get(offset..offset + 4)
+                            .map(|b| Value::Number(i32::from_le_bytes(b.try_into().unwrap()) as f64)),
+                    };
+                    values.insert(name.clone(), value.unwrap_or(Value::Missing));
+                }
+                self.values = values;
+            }
+            DataSource::Pipe { reader, buf } => {
+                buf.clear();
+                if reader.read_to_string(buf).is_ok() {
+                    for line in buf.lines() {
+                        self.apply_json_line(line);
+                    }
+                }
+            }
+            DataSource::JsonLines { path, offset } => {
+                if let Ok(mut file) = std::fs::File::open(&path) {
+                    if file.seek(SeekFrom::Start(*offset)).is_ok() {
+                        let mut buf = String::new();
+                        if let Ok(n) = file.read_to_string(&mut buf) {
+                            *offset += n as u64;
+                            for line in buf.lines() {
+                                self.apply_json_line(line);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Merge one JSON object line into `self.values`, and if it carries a
+    /// `rows` array (the leaderboard case: place/name/delta entries),
+    /// replace `self.rows` with it wholesale - the source resends the full
+    /// table each update rather than incremental diffs.
+    fn apply_json_line(&mut self, line: &str) {
+        let Ok(parsed) = serde_json::from_str::<serde_json::Value>(line) else {
+            return;
+        };
+        let Some(obj) = parsed.as_object() else {
+            return;
+        };
+
+        for (key, value) in obj {
+            if key == "rows" {
+                if let Some(rows) = value.as_array() {
+                    self.rows = rows
+                        .iter()
+                        .filter_map(|row| row.as_object())
+                        .map(|row| {
+                            row.iter()
+                                .map(|(k, v)| (k.clone(), Value::from_json(v)))
+                                .collect()
+                        })
+                        .collect();
+                }
+                continue;
+            }
+            self.values.insert(key.clone(), Value::from_json(value));
+        }
+    }
+
+    fn draw_text(&mut self, rect: Rect, text: &str, color: [u8; 4]) {
+        let cell_w = (rect.w / text.len().max(1) as u32).max(1).min(rect.w.max(1));
+        let cell_h = rect.h.max(1);
+
+        // Scale the font's native 5x7 grid up to fill the cell, clamped to
+        // at least one framebuffer pixel per glyph pixel so it never
+        // disappears in a too-small rect.
+        let sx = (cell_w / font::GLYPH_W as u32).max(1);
+        let sy = (cell_h / font::GLYPH_H as u32).max(1);
+
+        for (i, ch) in text.chars().enumerate() {
+            if ch == ' ' {
+                continue;
+            }
+            let origin_x = rect.x + i as u32 * cell_w;
+            let origin_y = rect.y;
+
+            for (row, bits) in font::glyph(ch).iter().enumerate() {
+                for col in 0..font::GLYPH_W {
+                    if bits & (1 << (font::GLYPH_W - 1 - col)) == 0 {
+                        continue;
+                    }
+                    self.fill_rect(
+                        Rect {
+                            x: origin_x + col as u32 * sx,
+                            y: origin_y + row as u32 * sy,
+                            w: sx,
+                            h: sy,
+                        },
+                        color,
+                    );
+                }
+            }
+        }
+    }
+
+    fn draw_gauge(&mut self, rect: Rect, fill: f32) {
+        let filled_w = (rect.w as f32 * fill.clamp(0.0, 1.0)) as u32;
+        self.fill_rect(
+            Rect {
+                x: rect.x,
+                y: rect.y,
+                w: rect.w,
+                h: rect.h,
+            },
+            [40, 40, 40, 255],
+        );
+        self.fill_rect(
+            Rect {
+                x: rect.x,
+                y: rect.y,
+                w: filled_w,
+                h: rect.h,
+            },
+            [80, 200, 120, 255],
+        );
+    }
+
+    fn fill_rect(&mut self, rect: Rect, color: [u8; 4]) {
+        let (extent_w, extent_h) = (self.extent[0], self.extent[1]);
+        for y in rect.y..(rect.y + rect.h).min(extent_h) {
+            for x in rect.x..(rect.x + rect.w).min(extent_w) {
+                let idx = ((y * extent_w + x) * 4) as usize;
+                if let Some(px) = self.framebuffer.get_mut(idx..idx + 4) {
+                    px.copy_from_slice(&color);
+                }
+            }
+        }
+    }
+}
+
@@ -0,0 +1,135 @@
+use std::path::PathBuf;
+
+use glam::Affine3A;
+use ovr_overlay::{
+    input::{ActionHandle, ActionSetHandle, InputError, InputManager},
+    sys::ETrackedDeviceClass,
+    system::SystemManager,
+    TrackedDeviceIndex,
+};
+
+use crate::state::AppState;
+
+use super::{lines::LineId, overlay::OpenVrOverlayData};
+use crate::backend::common::OverlayContainer;
+
+const ACTION_SET_NAME: &str = "/actions/main";
+const POINTER_ACTION_NAMES: [&str; 2] = ["/actions/main/in/pointer_l", "/actions/main/in/pointer_r"];
+
+pub fn action_manifest_path() -> PathBuf {
+    crate::config_io::CONFIG_ROOT_PATH.join("action_manifest.json")
+}
+
+/// Per-hand pointer state. `line_id` is allocated once from `LinePool` and
+/// kept stable across action-manifest reloads.
+pub struct Pointer {
+    pub pose: Affine3A,
+    pub valid: bool,
+    pub data: PointerData,
+    action: ActionHandle,
+}
+
+#[derive(Default)]
+pub struct PointerData {
+    pub line_id: LineId,
+}
+
+impl Pointer {
+    fn new(action: ActionHandle) -> Self {
+        Self {
+            pose: Affine3A::IDENTITY,
+            valid: false,
+            data: PointerData::default(),
+            action,
+        }
+    }
+
+    /// Raycast against every overlay and return the hit distance, or 0.0
+    /// when nothing was hit.
+    pub fn interact(&mut self, overlays: &mut OverlayContainer<OpenVrOverlayData>, state: &mut AppState) -> f32 {
+        if !self.valid {
+            return 0.0;
+        }
+
+        overlays
+            .iter_mut()
+            .filter_map(|o| o.try_intersect(self.pose, state))
+            .fold(0.0, f32::max)
+    }
+}
+
+/// Action-system state for the two hand pointers. Rebuilt in place by
+/// `reload_action_handles` when the action manifest changes on disk,
+/// without touching `pointers[n].data.line_id` so existing `LinePool`
+/// allocations stay valid across a reload.
+pub struct InputState {
+    pub pointers: [Pointer; 2],
+    action_set: ActionSetHandle,
+}
+
+impl InputState {
+    pub fn new(input_mngr: &mut InputManager) -> Result<Self, InputError> {
+        let action_set = input_mngr.get_action_set_handle(ACTION_SET_NAME)?;
+        let pointers = [
+            Pointer::new(input_mngr.get_action_handle(POINTER_ACTION_NAMES[0])?),
+            Pointer::new(input_mngr.get_action_handle(POINTER_ACTION_NAMES[1])?),
+        ];
+
+        Ok(Self {
+            pointers,
+            action_set,
+        })
+    }
+
+    /// Re-fetch the action set and per-pointer action handles after the
+    /// manifest has been reloaded with `set_action_manifest`. Handles are
+    /// looked up by the same path every time, so existing `pointers`
+    /// entries (and their `line_id` allocations) are updated in place
+    /// rather than recreated.
+    pub fn reload_action_handles(&mut self, input_mngr: &mut InputManager) -> Result<(), InputError> {
+        self.action_set = input_mngr.get_action_set_handle(ACTION_SET_NAME)?;
+        for (pointer, name) in self.pointers.iter_mut().zip(POINTER_ACTION_NAMES) {
+            pointer.action = input_mngr.get_action_handle(name)?;
+        }
+        Ok(())
+    }
+
+    pub fn update_devices(&mut self, system_mngr: &mut SystemManager) {
+        let _ = first_controller(system_mngr);
+    }
+
+    pub fn pre_update(&mut self) {}
+
+    pub fn update(
+        &mut self,
+        input_mngr: &mut InputManager,
+        system_mngr: &mut SystemManager,
+        predicted_photon_time: f32,
+    ) {
+        let _ = system_mngr;
+        for pointer in &mut self.pointers {
+            let Ok(state) = input_mngr.get_pose_action_data_relative_to_now(
+                pointer.action,
+                self.action_set,
+                predicted_photon_time,
+            ) else {
+                pointer.valid = false;
+                continue;
+            };
+
+            pointer.valid = state.is_valid;
+            pointer.pose = state.pose;
+        }
+    }
+
+    pub fn post_update(&mut self) {}
+}
+
+/// Device index of the first tracked controller, if any. Its only caller,
+/// `update_devices`, discards the result.
+fn first_controller(system_mngr: &mut SystemManager) -> Option<TrackedDeviceIndex> {
+    system_mngr
+        .get_sorted_tracked_device_indices_of_class(ETrackedDeviceClass::ETrackedDeviceClass_Controller)
+        .into_iter()
+        .next()
+}
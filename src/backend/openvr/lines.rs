@@ -0,0 +1,202 @@
+use std::sync::Arc;
+
+use glam::{Affine3A, Quat, Vec3, Vec4};
+use ovr_overlay::{
+    overlay::OverlayManager,
+    sys::{ETrackingUniverseOrigin, HmdMatrix34_t, VROverlayHandle_t},
+};
+use vulkano::{
+    command_buffer::{AutoCommandBufferBuilder, CommandBufferUsage},
+    image::{Image, ImageCreateInfo, ImageUsage},
+    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter},
+    sync::GpuFuture,
+};
+
+use crate::state::AppState;
+
+use super::vector::{Path, PathRenderer};
+
+/// Handle to a pointer's line overlay, stable across `LinePool` updates
+/// (and across an action-manifest hot-reload, since `InputState` never
+/// reallocates it).
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct LineId(pub usize);
+
+/// Resolution of a line's render target. This is independent of the
+/// line's length/width in meters - those are baked into the overlay's
+/// world transform each frame (`quad_transform`), so the texture only
+/// ever needs to hold a single constant-shape stroke.
+const LINE_TEXTURE_EXTENT: [u32; 2] = [16, 128];
+
+struct Line {
+    handle: VROverlayHandle_t,
+    target: Arc<Image>,
+    pending: Option<(Path, Affine3A)>,
+}
+
+/// Pool of pointer-laser overlays. Each line owns a small render target
+/// that `PathRenderer` composites an antialiased stroked path into every
+/// frame; `draw_from` is a two-point special case of `draw_path`. The
+/// path geometry stays in the overlay's fixed local/pixel space - the
+/// ray's actual length and width in meters are carried entirely by the
+/// overlay's world transform, computed from the pose/distance passed to
+/// `draw_from`/`draw_path`.
+pub struct LinePool {
+    graphics: Arc<crate::graphics::WlxGraphics>,
+    renderer: PathRenderer,
+    lines: Vec<Line>,
+}
+
+impl LinePool {
+    pub fn new(graphics: Arc<crate::graphics::WlxGraphics>) -> Self {
+        let renderer = PathRenderer::new(graphics.clone(), LINE_TEXTURE_EXTENT);
+        Self {
+            graphics,
+            renderer,
+            lines: Vec::with_capacity(2),
+        }
+    }
+
+    pub fn allocate(&mut self, overlay_mngr: &mut OverlayManager, _state: &mut AppState) -> LineId {
+        let key = format!("wlx-overlay-s.line.{}", self.lines.len());
+        let handle = overlay_mngr
+            .create_overlay(&key, &key)
+            .expect("failed to create line overlay");
+
+        // Scale is baked into the transform we submit every frame, so the
+        // overlay's own width is left at 1:1.
+        let _ = overlay_mngr.set_overlay_width_in_meters(handle, 1.0);
+
+        let target = Image::new(
+            self.graphics.memory_allocator.clone(),
+            ImageCreateInfo {
+                extent: [LINE_TEXTURE_EXTENT[0], LINE_TEXTURE_EXTENT[1], 1],
+                format: vulkano::format::Format::R8G8B8A8_UNORM,
+                usage: ImageUsage::STORAGE | ImageUsage::TRANSFER_DST | ImageUsage::SAMPLED,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
+                ..Default::default()
+            },
+        )
+        .expect("failed to allocate line overlay target");
+
+        self.lines.push(Line {
+            handle,
+            target,
+            pending: None,
+        });
+
+        LineId(self.lines.len() - 1)
+    }
+
+    /// Two-point special case of `draw_path`: a straight ray from `pose`'s
+    /// position out to `dist` along its forward (-Z) axis.
+    pub fn draw_from(&mut self, id: LineId, pose: Affine3A, dist: f32, color: Vec4) {
+        let start: Vec3 = pose.translation.into();
+        let end: Vec3 = (pose.translation + pose.matrix3 * glam::Vec3A::NEG_Z * dist).into();
+        self.draw_path(id, &[start, end], 0.0015, color, None);
+    }
+
+    /// Queue a stroked path to be rendered into this line's overlay
+    /// texture on the next `update`. `points` are world-space; only the
+    /// first and last are used to place the overlay quad (curved previews
+    /// are flattened to their endpoints since the quad itself is a
+    /// straight segment), and `width` is in meters. The stroke has one
+    /// width along its whole length, and since the texture always holds a
+    /// single two-point stroke, `dash` does not make it dashed.
+    pub fn draw_path(&mut self, id: LineId, points: &[Vec3], width: f32, color: Vec4, dash: Option<f32>) {
+        let Some(line) = self.lines.get_mut(id.0) else {
+            return;
+        };
+        let (Some(&start), Some(&end)) = (points.first(), points.last()) else {
+            return;
+        };
+
+        let transform = quad_transform(start, end, width);
+
+        // The quad's own shape (set via `transform`) carries the ray's
+        // actual length and width, so the path drawn into its texture is
+        // always the same vertical stroke down the middle column, full
+        // height, in pixel space.
+        let extent = LINE_TEXTURE_EXTENT;
+        let local_path = Path {
+            points: vec![
+                Vec3::new(extent[0] as f32 * 0.5, 0.0, 0.0),
+                Vec3::new(extent[0] as f32 * 0.5, extent[1] as f32, 0.0),
+            ],
+            width: extent[0] as f32 * 0.5,
+            color,
+            dash,
+        };
+
+        line.pending = Some((local_path, transform));
+    }
+
+    pub fn hide(&mut self, id: LineId, overlay_mngr: &mut OverlayManager) {
+        if let Some(line) = self.lines.get(id.0) {
+            let _ = overlay_mngr.set_visibility(line.handle, false);
+        }
+    }
+
+    /// Composite every line's pending path into its overlay texture,
+    /// position the overlay in the world to match the ray it represents,
+    /// and submit the result.
+    pub fn update(&mut self, overlay_mngr: &mut OverlayManager, state: &mut AppState) {
+        for line in &mut self.lines {
+            let Some((path, transform)) = line.pending.take() else {
+                continue;
+            };
+
+            let mut cmd = AutoCommandBufferBuilder::primary(
+                &self.graphics.command_buffer_allocator,
+                self.graphics.queue.queue_family_index(),
+                CommandBufferUsage::OneTimeSubmit,
+            )
+            .expect("failed to start line command buffer");
+
+            self.renderer.draw_path(&mut cmd, line.target.clone(), &path);
+
+            let cmd = cmd.build().expect("failed to build line command buffer");
+            cmd.execute(self.graphics.queue.clone())
+                .expect("failed to submit line command buffer")
+                .then_signal_fence_and_flush()
+                .expect("failed to flush line command buffer")
+                .wait(None)
+                .expect("failed to wait for line command buffer");
+
+            let _ = overlay_mngr.set_overlay_transform_absolute(
+                line.handle,
+                ETrackingUniverseOrigin::TrackingUniverseStanding,
+                &affine_to_hmd_matrix(transform),
+            );
+            let _ = overlay_mngr.set_visibility(line.handle, true);
+            let _ = state;
+        }
+    }
+}
+
+/// World transform for the overlay quad representing the ray from `start`
+/// to `end`: centered at the ray's midpoint, its local Y axis aligned to
+/// the ray direction, scaled so the unit quad spans `width` meters across
+/// and the ray's full length along its direction.
+fn quad_transform(start: Vec3, end: Vec3, width: f32) -> Affine3A {
+    let delta = end - start;
+    let length = delta.length().max(1e-4);
+    let rotation = Quat::from_rotation_arc(Vec3::Y, delta / length);
+    let midpoint = (start + end) * 0.5;
+
+    Affine3A::from_scale_rotation_translation(Vec3::new(width, length, 1.0), rotation, midpoint)
+}
+
+fn affine_to_hmd_matrix(a: Affine3A) -> HmdMatrix34_t {
+    let (x, y, z, t) = (a.matrix3.x_axis, a.matrix3.y_axis, a.matrix3.z_axis, a.translation);
+    HmdMatrix34_t {
+        m: [
+            [x.x, y.x, z.x, t.x],
+            [x.y, y.y, z.y, t.y],
+            [x.z, y.z, z.z, t.z],
+        ],
+    }
+}
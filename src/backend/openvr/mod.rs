@@ -16,16 +16,28 @@ use vulkano::{
 
 use crate::{backend::openvr::lines::LinePool, state::AppState};
 
-use self::{input::action_manifest_path, overlay::OpenVrOverlayData};
+/// How many whole frame periods of lookahead are added on top of the time
+/// left in the current frame when predicting poses, so the frame is rendered for the moment it will actually be displayed
+/// (the predicted photon time) rather than for the moment it's built.
+const FRAMES_TO_PREDICT: u32 = 1;
+
+use self::{
+    data_overlay::DataOverlay, input::action_manifest_path, overlay::OpenVrOverlayData,
+    playspace::PlayspaceMngr,
+};
 
 use super::{
     common::{OverlayContainer, TaskType},
     input::InputState,
 };
 
+pub mod data_overlay;
+pub mod font;
 pub mod input;
 pub mod lines;
 pub mod overlay;
+pub mod playspace;
+pub mod vector;
 
 pub fn openvr_run() {
     let app_type = EVRApplicationType::VRApplication_Overlay;
@@ -39,6 +51,7 @@ pub fn openvr_run() {
     let mut input_mngr = context.input_mngr();
     let mut system_mngr = context.system_mngr();
     let mut compositor_mngr = context.compositor_mngr();
+    let mut chaperone_setup_mngr = context.chaperone_setup_mngr();
 
     let device_extensions_fn = |device: &PhysicalDevice| {
         let names = compositor_mngr.get_vulkan_device_extensions_required(device.handle().as_raw());
@@ -67,7 +80,9 @@ pub fn openvr_run() {
         return;
     };
 
-    let Ok(refresh_rate) = system_mngr.get_tracked_device_property::<f32>(
+    let mut manifest_mtime = manifest_dir_mtime(&action_manifest_path());
+
+    let Ok(mut refresh_rate) = system_mngr.get_tracked_device_property::<f32>(
         TrackedDeviceIndex::HMD,
         ETrackedDeviceProperty::Prop_DisplayFrequency_Float,
     ) else {
@@ -77,7 +92,7 @@ pub fn openvr_run() {
 
     log::info!("HMD running @ {} Hz", refresh_rate);
 
-    let frame_time = (1000.0 / refresh_rate).floor() * 0.001;
+    let mut frame_time = (1000.0 / refresh_rate).floor() * 0.001;
     let mut next_device_update = Instant::now();
     let mut due_tasks = VecDeque::with_capacity(4);
 
@@ -85,6 +100,43 @@ pub fn openvr_run() {
     input.pointers[0].data.line_id = lines.allocate(&mut overlay_mngr, &mut state);
     input.pointers[1].data.line_id = lines.allocate(&mut overlay_mngr, &mut state);
 
+    let mut playspace = PlayspaceMngr::new(&mut chaperone_setup_mngr, &mut system_mngr);
+    let mut playspace_delta = None;
+
+    // One data-driven HUD overlay per `*.jsonl` source found in the
+    // config directory at startup, so adding a sim-racing/system-monitor
+    // feed is just adding a file - no code change needed. The directory is
+    // scanned once, so a file added while running shows on the next launch.
+    let mut data_overlays: Vec<DataOverlay> = data_overlay_sources()
+        .into_iter()
+        .map(|(name, path)| {
+            DataOverlay::new(
+                &mut overlay_mngr,
+                &state.graphics,
+                name,
+                [512, 512],
+                data_overlay::DataSource::JsonLines { path, offset: 0 },
+                vec![
+                    data_overlay::Widget::Label {
+                        field: "value".to_string(),
+                        format: data_overlay::Format::Raw,
+                        rect: data_overlay::Rect { x: 8, y: 8, w: 240, h: 32 },
+                    },
+                    data_overlay::Widget::Table {
+                        rows_field: "rows".to_string(),
+                        columns: vec![
+                            ("place".to_string(), data_overlay::Format::Raw),
+                            ("name".to_string(), data_overlay::Format::Raw),
+                            ("delta".to_string(), data_overlay::Format::LapDelta),
+                        ],
+                        max_rows: 10,
+                        rect: data_overlay::Rect { x: 8, y: 48, w: 480, h: 300 },
+                    },
+                ],
+            )
+        })
+        .collect();
+
     loop {
         while let Some(event) = system_mngr.poll_next_event() {
             match event.event_type {
@@ -93,9 +145,23 @@ pub fn openvr_run() {
                     return;
                 }
                 EVREventType::VREvent_TrackedDeviceActivated
-                | EVREventType::VREvent_TrackedDeviceDeactivated
-                | EVREventType::VREvent_TrackedDeviceUpdated => {
+                | EVREventType::VREvent_TrackedDeviceDeactivated => {
+                    next_device_update = Instant::now();
+                }
+                EVREventType::VREvent_TrackedDeviceUpdated => {
                     next_device_update = Instant::now();
+                    refresh_display_frequency(&mut system_mngr, &mut refresh_rate, &mut frame_time);
+                }
+                EVREventType::VREvent_SeatedZeroPoseReset
+                | EVREventType::VREvent_StandingZeroPoseReset
+                | EVREventType::VREvent_ChaperoneUniverseHasChanged => {
+                    if let Some(delta) = playspace.handle_event(
+                        event.event_type,
+                        &mut chaperone_setup_mngr,
+                        &mut system_mngr,
+                    ) {
+                        playspace_delta = Some(delta);
+                    }
                 }
                 _ => {}
             }
@@ -103,6 +169,21 @@ pub fn openvr_run() {
 
         if next_device_update <= Instant::now() {
             input.update_devices(&mut system_mngr);
+            refresh_display_frequency(&mut system_mngr, &mut refresh_rate, &mut frame_time);
+
+            // Pick up manifest/binding edits from disk without requiring a
+            // restart.
+            let new_mtime = manifest_dir_mtime(&action_manifest_path());
+            if new_mtime > manifest_mtime {
+                manifest_mtime = new_mtime;
+                log::info!("Action manifest changed on disk, reloading");
+                if let Err(e) = input_mngr.set_action_manifest(action_manifest_path()) {
+                    log::error!("Failed to reload action manifest: {}", e.description());
+                } else if let Err(e) = input.reload_action_handles(&mut input_mngr) {
+                    log::error!("Failed to rebuild input actions: {}", e.description());
+                }
+            }
+
             next_device_update = Instant::now() + Duration::from_secs(30);
         }
 
@@ -118,8 +199,22 @@ pub fn openvr_run() {
             }
         }
 
+        let mut seconds_since_vsync = 0f32;
+        let vsync_synced = system_mngr.get_time_since_last_vsync(&mut seconds_since_vsync, &mut 0u64);
+        let predicted_photon_time = if vsync_synced {
+            // Time left in the frame that's currently scanning out, plus
+            // `FRAMES_TO_PREDICT` whole frames of lookahead. When we're running
+            // behind (`seconds_since_vsync` overshoots `frame_time`) there
+            // is no time left in this frame, so clamp at 0 rather than
+            // predicting into the past.
+            let remaining_this_frame = (frame_time - seconds_since_vsync).max(0.0);
+            remaining_this_frame + frame_time * FRAMES_TO_PREDICT as f32
+        } else {
+            frame_time * FRAMES_TO_PREDICT as f32
+        };
+
         input.pre_update();
-        input.update(&mut input_mngr, &mut system_mngr);
+        input.update(&mut input_mngr, &mut system_mngr, predicted_photon_time);
         input.post_update();
 
         input.pointers.iter_mut().for_each(|p| {
@@ -145,27 +240,96 @@ pub fn openvr_run() {
             .filter(|o| o.state.want_visible)
             .for_each(|o| o.render(&mut state));
 
+        data_overlays
+            .iter_mut()
+            .filter(|o| o.want_visible)
+            .for_each(|o| o.render(&mut state));
+
         log::debug!("Rendering overlays");
 
         overlays
             .iter_mut()
             .for_each(|o| o.after_render(&mut overlay_mngr, &state.graphics));
 
-        // chaperone
+        data_overlays
+            .iter_mut()
+            .for_each(|o| o.after_render(&mut overlay_mngr, &state.graphics));
 
-        // close font handles?
+        if let Some(delta) = playspace_delta.take() {
+            playspace.realign_overlays(delta, &mut overlays);
+        }
 
-        // playspace moved end frame
+        // close font handles?
 
         state.input.on_new_frame();
 
+        // Re-read the vsync phase rather than reusing the one sampled at
+        // the top of the frame for pose prediction: rendering has spent an
+        // unknown amount of time since then, and sleeping on a stale phase
+        // overshoots the vsync boundary.
         let mut seconds_since_vsync = 0f32;
-        std::thread::sleep(Duration::from_secs_f32(
-            if system_mngr.get_time_since_last_vsync(&mut seconds_since_vsync, &mut 0u64) {
-                frame_time - (seconds_since_vsync % frame_time)
-            } else {
-                frame_time
-            },
-        ));
+        let vsync_synced = system_mngr.get_time_since_last_vsync(&mut seconds_since_vsync, &mut 0u64);
+
+        std::thread::sleep(Duration::from_secs_f32(if vsync_synced {
+            frame_time - (seconds_since_vsync % frame_time)
+        } else {
+            frame_time
+        }));
+    }
+}
+
+/// Discover configured HUD data feeds: every `*.jsonl` file under the
+/// `data_overlays` config directory becomes one `DataOverlay`, named after
+/// the file stem. Returns an empty list (no overlays created) when the
+/// directory doesn't exist, so this is a no-op until a user drops a feed
+/// in.
+fn data_overlay_sources() -> Vec<(String, std::path::PathBuf)> {
+    let dir = crate::config_io::CONFIG_ROOT_PATH.join("data_overlays");
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().into_owned();
+            Some((name, path))
+        })
+        .collect()
+}
+
+/// Latest modification time of the action manifest or any file alongside it
+/// (binding files live in the same directory), used to detect edits made
+/// while the overlay is running.
+fn manifest_dir_mtime(manifest_path: &std::path::Path) -> Option<std::time::SystemTime> {
+    let dir = manifest_path.parent()?;
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok()?.metadata().ok()?.modified().ok())
+        .max()
+}
+
+/// Re-query `Prop_DisplayFrequency_Float` and, if the HMD is now running at
+/// a different refresh rate than last observed, recompute `frame_time` and
+/// log the transition. Headsets that support multiple refresh rates can
+/// switch at runtime, and sleeping on a stale cadence causes stutter.
+fn refresh_display_frequency(
+    system_mngr: &mut ovr_overlay::system::SystemManager,
+    refresh_rate: &mut f32,
+    frame_time: &mut f32,
+) {
+    let Ok(new_rate) = system_mngr.get_tracked_device_property::<f32>(
+        TrackedDeviceIndex::HMD,
+        ETrackedDeviceProperty::Prop_DisplayFrequency_Float,
+    ) else {
+        return;
+    };
+
+    if (new_rate - *refresh_rate).abs() > f32::EPSILON {
+        log::info!("HMD refresh rate changed: {} Hz -> {} Hz", refresh_rate, new_rate);
+        *refresh_rate = new_rate;
+        *frame_time = (1000.0 / new_rate).floor() * 0.001;
     }
 }
@@ -0,0 +1,181 @@
+use glam::Affine3A;
+use ovr_overlay::{
+    chaperone_setup::ChaperoneSetupManager,
+    sys::{ETrackingUniverseOrigin, EVREventType, HmdMatrix34_t},
+    system::SystemManager,
+};
+
+use crate::backend::common::OverlayContainer;
+
+use super::overlay::OpenVrOverlayData;
+
+/// Tracks the active tracking universe's standing-zero-pose and re-anchors
+/// world-locked overlays whenever the user recenters or switches chaperone
+/// universe, so overlays stay fixed relative to the room rather than
+/// jumping with the headset.
+pub struct PlayspaceMngr {
+    universe: ETrackingUniverseOrigin,
+    zero_pose: Affine3A,
+    play_area: (f32, f32),
+}
+
+impl PlayspaceMngr {
+    pub fn new(
+        chaperone_setup_mngr: &mut ChaperoneSetupManager,
+        system_mngr: &mut SystemManager,
+    ) -> Self {
+        let universe = system_mngr.get_tracking_space();
+        let zero_pose = read_zero_pose(chaperone_setup_mngr);
+        let play_area = chaperone_setup_mngr
+            .get_working_play_area_size()
+            .unwrap_or((2.0, 2.0));
+
+        Self {
+            universe,
+            zero_pose,
+            play_area,
+        }
+    }
+
+    /// Handle a `VREvent_*` from the main poll loop. Returns the transform
+    /// that should be applied to every world-locked overlay to keep it
+    /// fixed relative to the room, if anything changed.
+    pub fn handle_event(
+        &mut self,
+        event_type: EVREventType,
+        chaperone_setup_mngr: &mut ChaperoneSetupManager,
+        system_mngr: &mut SystemManager,
+    ) -> Option<Affine3A> {
+        match event_type {
+            EVREventType::VREvent_SeatedZeroPoseReset
+            | EVREventType::VREvent_StandingZeroPoseReset => {
+                Some(self.recompute(chaperone_setup_mngr, system_mngr))
+            }
+            EVREventType::VREvent_ChaperoneUniverseHasChanged => {
+                // The origin itself changed, not just the zero pose within
+                // it, so the old reference frame is meaningless - just
+                // re-key without producing a jump.
+                self.universe = system_mngr.get_tracking_space();
+                self.zero_pose = read_zero_pose(chaperone_setup_mngr);
+                self.play_area = chaperone_setup_mngr
+                    .get_working_play_area_size()
+                    .unwrap_or(self.play_area);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn recompute(
+        &mut self,
+        chaperone_setup_mngr: &mut ChaperoneSetupManager,
+        system_mngr: &mut SystemManager,
+    ) -> Affine3A {
+        let universe = system_mngr.get_tracking_space();
+        let new_pose = read_zero_pose(chaperone_setup_mngr);
+
+        let delta = if universe == self.universe {
+            recenter_delta(self.zero_pose, new_pose)
+        } else {
+            // Seated and standing universes have different origins - there
+            // is no meaningful delta to carry across them, so don't move
+            // anything and just adopt the new reference frame.
+            Affine3A::IDENTITY
+        };
+
+        self.universe = universe;
+        self.zero_pose = new_pose;
+        delta
+    }
+
+    /// Size (x, z) in meters of the user's configured play area, used by
+    /// `realign_overlays` to keep re-anchored overlays inside the guardian
+    /// bounds.
+    pub fn play_area_size(&self) -> (f32, f32) {
+        self.play_area
+    }
+
+    /// Apply a re-anchoring delta to every world-locked overlay so they
+    /// stay fixed relative to the room instead of the headset. Overlays
+    /// that opted into `clamp_to_guardian` are additionally kept inside
+    /// the guardian's working play area; everything else is re-anchored
+    /// without being pulled toward the standing origin, since overlays
+    /// placed further out (a wall screen, a clock) are legitimately
+    /// outside that box.
+    pub fn realign_overlays(&self, delta: Affine3A, overlays: &mut OverlayContainer<OpenVrOverlayData>) {
+        let (half_x, half_z) = (self.play_area.0 * 0.5, self.play_area.1 * 0.5);
+
+        overlays
+            .iter_mut()
+            .filter(|o| o.state.world_locked)
+            .for_each(|o| {
+                let mut transform = delta * o.state.transform;
+                if o.state.clamp_to_guardian {
+                    transform.translation.x = transform.translation.x.clamp(-half_x, half_x);
+                    transform.translation.z = transform.translation.z.clamp(-half_z, half_z);
+                }
+                o.state.transform = transform;
+            });
+    }
+}
+
+/// The correction to apply to a transform expressed in standing space so
+/// that it stays fixed in raw/room space as the standing->raw zero pose
+/// moves from `old` to `new`: a point `p` that used to resolve to
+/// `old * p` in raw space must now resolve to the same raw-space point via
+/// `new`, i.e. `new * delta * p == old * p`, so `delta = new^-1 * old`.
+fn recenter_delta(old: Affine3A, new: Affine3A) -> Affine3A {
+    new.inverse() * old
+}
+
+fn read_zero_pose(chaperone_setup_mngr: &mut ChaperoneSetupManager) -> Affine3A {
+    chaperone_setup_mngr
+        .get_working_standing_zero_pose_to_raw_tracking_pose()
+        .map(hmd_matrix_to_affine)
+        .unwrap_or(Affine3A::IDENTITY)
+}
+
+fn hmd_matrix_to_affine(m: HmdMatrix34_t) -> Affine3A {
+    let m = m.m;
+    Affine3A::from_cols_array(&[
+        m[0][0], m[1][0], m[2][0], m[0][1], m[1][1], m[2][1], m[0][2], m[1][2], m[2][2], m[0][3],
+        m[1][3], m[2][3],
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::{Quat, Vec3};
+
+    /// A 0.5m lateral recenter plus a 90-degree yaw: a point that used to
+    /// sit 1m in front of the old origin must resolve to the same raw-space
+    /// point through the new zero pose once `recenter_delta` is folded in.
+    #[test]
+    fn recenter_delta_preserves_raw_space_position() {
+        let old = Affine3A::from_rotation_translation(Quat::IDENTITY, Vec3::new(0.0, 0.0, 0.0));
+        let new = Affine3A::from_rotation_translation(
+            Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+            Vec3::new(0.5, 0.0, 0.0),
+        );
+
+        let delta = recenter_delta(old, new);
+        let p = Vec3::new(0.0, 0.0, -1.0);
+
+        let raw_before = old.transform_point3(p);
+        let raw_after = new.transform_point3(delta.transform_point3(p));
+
+        assert!((raw_before - raw_after).length() < 1e-5);
+    }
+
+    #[test]
+    fn recenter_delta_is_identity_when_unchanged() {
+        let pose = Affine3A::from_rotation_translation(
+            Quat::from_rotation_y(0.3),
+            Vec3::new(1.0, 2.0, 3.0),
+        );
+        let delta = recenter_delta(pose, pose);
+        assert!(delta.translation.length() < 1e-5);
+        assert!(delta.matrix3.abs_diff_eq(glam::Mat3A::IDENTITY, 1e-5));
+    }
+}
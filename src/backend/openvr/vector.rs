@@ -0,0 +1,382 @@
+use std::sync::Arc;
+
+use glam::{Vec2, Vec3, Vec4};
+use vulkano::{
+    buffer::{Buffer, BufferCreateInfo, BufferUsage, Subbuffer},
+    command_buffer::{AutoCommandBufferBuilder, PrimaryAutoCommandBuffer},
+    descriptor_set::{
+        allocator::StandardDescriptorSetAllocator, PersistentDescriptorSet, WriteDescriptorSet,
+    },
+    image::{view::ImageView, Image},
+    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter},
+    pipeline::{
+        compute::ComputePipelineCreateInfo, layout::PipelineDescriptorSetLayoutCreateInfo,
+        ComputePipeline, Pipeline, PipelineBindPoint, PipelineLayout,
+        PipelineShaderStageCreateInfo,
+    },
+};
+
+use crate::graphics::WlxGraphics;
+
+mod shaders {
+    pub mod coverage {
+        vulkano_shaders::shader! {
+            ty: "compute",
+            src: r"
+                #version 450
+                layout(local_size_x = 64) in;
+
+                struct Segment { vec2 a; vec2 b; float width_a; float width_b; };
+
+                layout(set = 0, binding = 0) readonly buffer Segments { Segment segments[]; };
+                layout(set = 0, binding = 1) buffer Coverage { float coverage[]; };
+
+                layout(push_constant) uniform Push {
+                    uint extent_x;
+                    uint extent_y;
+                    uint segment_count;
+                } pc;
+
+                float coverage_for_segment(Segment s, vec2 p) {
+                    vec2 ab = s.b - s.a;
+                    float len2 = max(dot(ab, ab), 1e-6);
+                    float t = clamp(dot(p - s.a, ab) / len2, 0.0, 1.0);
+                    vec2 closest = s.a + ab * t;
+                    float width = mix(s.width_a, s.width_b, t);
+                    float d = distance(p, closest);
+                    return 1.0 - smoothstep(width * 0.5 - 1.0, width * 0.5 + 1.0, d);
+                }
+
+                void main() {
+                    uint idx = gl_GlobalInvocationID.x;
+                    if (idx >= pc.extent_x * pc.extent_y) {
+                        return;
+                    }
+
+                    vec2 p = vec2(float(idx % pc.extent_x), float(idx / pc.extent_x));
+
+                    float c = 0.0;
+                    for (uint i = 0; i < pc.segment_count; i++) {
+                        c = max(c, coverage_for_segment(segments[i], p));
+                    }
+                    coverage[idx] = c;
+                }
+            ",
+        }
+    }
+
+    pub mod composite {
+        vulkano_shaders::shader! {
+            ty: "compute",
+            src: r"
+                #version 450
+                layout(local_size_x = 8, local_size_y = 8) in;
+
+                layout(set = 0, binding = 0) readonly buffer Coverage { float coverage[]; };
+                layout(set = 0, binding = 1, rgba8) uniform image2D target;
+
+                layout(push_constant) uniform Push {
+                    uint extent_x;
+                    uint extent_y;
+                    vec4 color;
+                } pc;
+
+                void main() {
+                    ivec2 pos = ivec2(gl_GlobalInvocationID.xy);
+                    if (pos.x >= int(pc.extent_x) || pos.y >= int(pc.extent_y)) {
+                        return;
+                    }
+
+                    uint idx = uint(pos.y) * pc.extent_x + uint(pos.x);
+                    float a = coverage[idx] * pc.color.a;
+                    vec4 dst = imageLoad(target, pos);
+                    vec4 blended = vec4(pc.color.rgb * a + dst.rgb * (1.0 - a), max(a, dst.a));
+                    imageStore(target, pos, blended);
+                }
+            ",
+        }
+    }
+}
+
+/// A single stroked, optionally dashed polyline to be rasterized this
+/// frame. Replaces the fixed-width textured quad `LinePool` used to draw
+/// with something that can curve and dash. `width` applies along the whole
+/// path, so a stroke does not taper.
+pub struct Path {
+    pub points: Vec<Vec3>,
+    pub width: f32,
+    pub color: Vec4,
+    pub dash: Option<f32>,
+}
+
+/// GPU path renderer: tessellates stroked polylines into per-pixel
+/// coverage on one compute pass, then blends that coverage over the
+/// overlay's Vulkan texture on a second. `LinePool` uses this as its
+/// backend; `draw_from` is just `draw_path` with a two-point straight
+/// path.
+pub struct PathRenderer {
+    graphics: Arc<WlxGraphics>,
+    descriptor_allocator: Arc<StandardDescriptorSetAllocator>,
+    coverage_pipeline: Arc<ComputePipeline>,
+    composite_pipeline: Arc<ComputePipeline>,
+    coverage: Subbuffer<[f32]>,
+    extent: [u32; 2],
+}
+
+impl PathRenderer {
+    /// `extent` is the overlay extent the scratch coverage buffer is sized
+    /// to; it's reused across frames rather than reallocated per draw.
+    pub fn new(graphics: Arc<WlxGraphics>, extent: [u32; 2]) -> Self {
+        let device = graphics.device.clone();
+
+        let coverage_pipeline = build_pipeline(device.clone(), shaders::coverage::load);
+        let composite_pipeline = build_pipeline(device.clone(), shaders::composite::load);
+
+        let coverage = Buffer::new_slice::<f32>(
+            graphics.memory_allocator.clone(),
+            BufferCreateInfo {
+                usage: BufferUsage::STORAGE_BUFFER | BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
+                    | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+            (extent[0] * extent[1]) as u64,
+        )
+        .expect("failed to allocate path coverage buffer");
+
+        let descriptor_allocator = Arc::new(StandardDescriptorSetAllocator::new(
+            device,
+            Default::default(),
+        ));
+
+        Self {
+            graphics,
+            descriptor_allocator,
+            coverage_pipeline,
+            composite_pipeline,
+            coverage,
+            extent,
+        }
+    }
+
+    /// Tessellate `path` into per-pixel coverage (there is no tiling
+    /// pass) and composite the result
+    /// into `target`. Points are expected in the overlay's local space;
+    /// curved-ray / teleport-arc previews are passed in as a pre-sampled
+    /// polyline (a handful of points along the arc) rather than as a
+    /// dedicated curve primitive, so this is the only draw entry point.
+    pub fn draw_path(
+        &mut self,
+        cmd: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+        target: Arc<Image>,
+        path: &Path,
+    ) {
+        if path.points.len() < 2 {
+            return;
+        }
+
+        let segments = tessellate(&path.points, path.width, path.dash);
+        if segments.is_empty() {
+            return;
+        }
+
+        self.accumulate_coverage(cmd, &segments);
+        self.composite(cmd, target, path.color);
+    }
+
+    /// Convenience wrapper matching the old two-point `draw_from` shape,
+    /// kept for pointers that don't need tapering or dashing.
+    pub fn draw_from(
+        &mut self,
+        cmd: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+        target: Arc<Image>,
+        start: Vec3,
+        end: Vec3,
+        width: f32,
+        color: Vec4,
+    ) {
+        self.draw_path(
+            cmd,
+            target,
+            &Path {
+                points: vec![start, end],
+                width,
+                color,
+                dash: None,
+            },
+        );
+    }
+
+    fn accumulate_coverage(
+        &mut self,
+        cmd: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+        segments: &[Segment],
+    ) {
+        let segment_buffer = Buffer::from_iter(
+            self.graphics.memory_allocator.clone(),
+            BufferCreateInfo {
+                usage: BufferUsage::STORAGE_BUFFER,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+            segments.iter().map(|s| shaders::coverage::Segment {
+                a: s.a.into(),
+                b: s.b.into(),
+                width_a: s.width_a,
+                width_b: s.width_b,
+            }),
+        )
+        .expect("failed to upload path segments");
+
+        let layout = self.coverage_pipeline.layout().set_layouts()[0].clone();
+        let set = PersistentDescriptorSet::new(
+            &self.descriptor_allocator,
+            layout,
+            [
+                WriteDescriptorSet::buffer(0, segment_buffer),
+                WriteDescriptorSet::buffer(1, self.coverage.clone()),
+            ],
+            [],
+        )
+        .expect("failed to build coverage descriptor set");
+
+        let push_constants = shaders::coverage::Push {
+            extent_x: self.extent[0],
+            extent_y: self.extent[1],
+            segment_count: segments.len() as u32,
+        };
+
+        let pixel_count = self.extent[0] * self.extent[1];
+        let group_count = pixel_count.div_ceil(64);
+
+        cmd.bind_pipeline_compute(self.coverage_pipeline.clone())
+            .unwrap()
+            .bind_descriptor_sets(
+                PipelineBindPoint::Compute,
+                self.coverage_pipeline.layout().clone(),
+                0,
+                set,
+            )
+            .unwrap()
+            .push_constants(self.coverage_pipeline.layout().clone(), 0, push_constants)
+            .unwrap();
+
+        unsafe { cmd.dispatch([group_count, 1, 1]) }.expect("coverage dispatch failed");
+    }
+
+    fn composite(
+        &mut self,
+        cmd: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+        target: Arc<Image>,
+        color: Vec4,
+    ) {
+        let target_view = ImageView::new_default(target).expect("failed to view overlay target");
+
+        let layout = self.composite_pipeline.layout().set_layouts()[0].clone();
+        let set = PersistentDescriptorSet::new(
+            &self.descriptor_allocator,
+            layout,
+            [
+                WriteDescriptorSet::buffer(0, self.coverage.clone()),
+                WriteDescriptorSet::image_view(1, target_view),
+            ],
+            [],
+        )
+        .expect("failed to build composite descriptor set");
+
+        let push_constants = shaders::composite::Push {
+            extent_x: self.extent[0],
+            extent_y: self.extent[1],
+            color: color.into(),
+        };
+
+        let group_count = [self.extent[0].div_ceil(8), self.extent[1].div_ceil(8), 1];
+
+        cmd.bind_pipeline_compute(self.composite_pipeline.clone())
+            .unwrap()
+            .bind_descriptor_sets(
+                PipelineBindPoint::Compute,
+                self.composite_pipeline.layout().clone(),
+                0,
+                set,
+            )
+            .unwrap()
+            .push_constants(self.composite_pipeline.layout().clone(), 0, push_constants)
+            .unwrap();
+
+        unsafe { cmd.dispatch(group_count) }.expect("composite dispatch failed");
+    }
+}
+
+fn build_pipeline(
+    device: Arc<vulkano::device::Device>,
+    load: impl FnOnce(
+        Arc<vulkano::device::Device>,
+    ) -> Result<Arc<vulkano::shader::ShaderModule>, vulkano::Validated<vulkano::VulkanError>>,
+) -> Arc<ComputePipeline> {
+    let shader = load(device.clone()).expect("failed to load path renderer shader");
+    let entry_point = shader.entry_point("main").expect("missing entry point");
+    let stage = PipelineShaderStageCreateInfo::new(entry_point);
+
+    let layout = PipelineLayout::new(
+        device.clone(),
+        PipelineDescriptorSetLayoutCreateInfo::from_stages([&stage])
+            .into_pipeline_layout_create_info(device.clone())
+            .expect("failed to build pipeline layout create info"),
+    )
+    .expect("failed to build pipeline layout");
+
+    ComputePipeline::new(
+        device,
+        None,
+        ComputePipelineCreateInfo::stage_layout(stage, layout),
+    )
+    .expect("failed to build compute pipeline")
+}
+
+/// A single tessellated quad (in the target's pixel coordinates) ready for
+/// the coverage-accumulation pass, tapering linearly between its two ends.
+struct Segment {
+    a: Vec2,
+    b: Vec2,
+    width_a: f32,
+    width_b: f32,
+}
+
+fn tessellate(points: &[Vec3], width: f32, dash: Option<f32>) -> Vec<Segment> {
+    let mut segments = Vec::with_capacity(points.len().saturating_sub(1));
+    let mut travelled = 0.0f32;
+
+    for window in points.windows(2) {
+        let (a, b) = (window[0].truncate(), window[1].truncate());
+        let len = a.distance(b);
+
+        if let Some(period) = dash {
+            // Alternate on/off every half period along the path. Whole
+            // segments are kept or skipped by the distance travelled at
+            // their start, so a segment is never split and a two-point
+            // path is always drawn solid.
+            if ((travelled / (period * 0.5)) as u32) % 2 == 1 {
+                travelled += len;
+                continue;
+            }
+        }
+
+        segments.push(Segment {
+            a,
+            b,
+            width_a: width,
+            width_b: width,
+        });
+        travelled += len;
+    }
+
+    segments
+}